@@ -1,35 +1,75 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Serialize as SerdeSerialize};
 use serde_derive::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_util::codec::{Decoder, Encoder};
 
 use crate::rpc_util::internal_error;
 
+/// Generic over the payload type `P`; defaults to `serde_bytes::ByteBuf` to
+/// preserve the historical opaque-bytes wire behavior.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
-pub enum Message {
-    Request {
-        id: String,
-        #[serde(with = "serde_bytes")]
-        data: Vec<u8>,
-    },
-    Response {
-        id: String,
-        #[serde(with = "serde_bytes")]
-        data: Vec<u8>,
-    },
-    Signal {
-        #[serde(with = "serde_bytes")]
-        data: Vec<u8>,
-    },
+#[serde(bound(serialize = "P: SerdeSerialize", deserialize = "P: DeserializeOwned"))]
+pub enum Message<P = serde_bytes::ByteBuf> {
+    Request { id: String, data: P },
+    Response { id: String, data: P },
+    Signal { data: P },
+}
+
+/// Each WebSocket binary frame carries exactly one encoded `Message`, so
+/// `decode` consumes whatever is in the buffer rather than tracking a length
+/// prefix itself.
+#[derive(Debug, Default)]
+pub struct HolochainCodec;
+
+impl Decoder for HolochainCodec {
+    type Item = Message;
+    type Error = String;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+        let buf = src.split();
+        rmp_serde::from_slice(&buf)
+            .map(Some)
+            .map_err(|e| format!("failed to parse response from conductor as MessagePack: {}", e))
+    }
+}
+
+impl Encoder<Message> for HolochainCodec {
+    type Error = String;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let bytes = rmp_serde::to_vec_named(&item).expect("serialization cannot fail");
+        dst.extend_from_slice(&bytes);
+        Ok(())
+    }
 }
 
 pub fn request(id: String, data_buf: Vec<u8>) -> Vec<u8> {
-    let msg = Message::Request { id, data: data_buf };
-    rmp_serde::to_vec_named(&msg).expect("serialization cannot fail")
+    request_typed(id, serde_bytes::ByteBuf::from(data_buf))
+        .expect("serialization cannot fail")
+}
+
+/// Like `request`, but generic over the payload type so a caller who knows the
+/// concrete shape of `data` can serialize it directly instead of pre-encoding
+/// it to MessagePack bytes themselves.
+pub fn request_typed<P: SerdeSerialize>(id: String, data: P) -> Result<Vec<u8>, String> {
+    let msg = Message::Request { id, data };
+    rmp_serde::to_vec_named(&msg).map_err(|e| format!("failed to encode conductor request: {}", e))
 }
 
 pub fn parse_holochain_message(message: ws::Message) -> Result<Message, String> {
@@ -45,102 +85,606 @@ pub fn parse_holochain_message(message: ws::Message) -> Result<Message, String>
     })
 }
 
-fn parse_holochain_response(response: ws::Message) -> Result<Vec<u8>, String> {
-    match parse_holochain_message(response)? {
+/// Parses a conductor response generic over the payload type: the frame is
+/// deserialized directly into `Message<P>` in one step instead of being
+/// extracted as raw bytes and then separately decoded by the caller. Returns
+/// the `data` of a `Message::Response`; any other variant is an error.
+pub fn parse_holochain_response_typed<P: DeserializeOwned + std::fmt::Debug>(
+    response: ws::Message,
+) -> Result<P, String> {
+    let response_buf = match response {
+        ws::Message::Binary(buf) => buf,
+        r => return Err(format!("unexpected response from conductor: {:?}", r)),
+    };
+    match rmp_serde::from_slice::<Message<P>>(&response_buf).map_err(|e| {
+        format!(
+            "failed to parse response from conductor as MessagePack: {}",
+            e
+        )
+    })? {
         Message::Response { data, .. } => Ok(data),
-        r => return Err(format!("unexpected message type from conductor: {:?}", r)),
+        r => Err(format!("unexpected message type from conductor: {:?}", r)),
     }
 }
 
+/// A single binary WebSocket frame decoded with `HolochainCodec`, or the error
+/// produced while reading or decoding it.
+type CodecResult = Result<Message, String>;
+
+/// Opens an async connection to a conductor interface as a `Stream<Item =
+/// CodecResult> + Sink<Message>`, so callers can `await` a request or consume
+/// signals as they arrive instead of spawning a thread with a lock-guarded
+/// callback.
+pub async fn connect_codec(
+    port: u16,
+) -> Result<
+    impl futures::Stream<Item = CodecResult> + futures::Sink<Message, Error = String>,
+    jsonrpc_core::Error,
+> {
+    let (ws_stream, _) = tokio_tungstenite::connect_async(format!("ws://localhost:{}", port))
+        .await
+        .map_err(|e| internal_error(format!("failed to connect to conductor interface: {}", e)))?;
+
+    let codec_stream = ws_stream
+        .filter_map(|frame| async move {
+            match frame {
+                Ok(WsMessage::Binary(buf)) => {
+                    let mut bytes = BytesMut::from(&buf[..]);
+                    HolochainCodec.decode(&mut bytes).transpose()
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(format!(
+                    "error reading from conductor interface: {}",
+                    e
+                ))),
+            }
+        })
+        // `with`'s error type must implement `From<Self::Error>`, and there's no
+        // `From<tungstenite::Error> for String`, so map the sink error first.
+        .sink_map_err(|e| e.to_string())
+        .with(|msg: Message| async move {
+            let mut bytes = BytesMut::new();
+            HolochainCodec.encode(msg, &mut bytes)?;
+            Ok::<_, String>(WsMessage::Binary(bytes.to_vec()))
+        });
+
+    Ok(codec_stream)
+}
+
+/// Default timeout for `remote_call`.
+const DEFAULT_REMOTE_CALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Backoff between retries is `RETRY_BACKOFF * attempt_number`.
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
 pub fn remote_call(port: u16, data_buf: Vec<u8>) -> Result<Vec<u8>, jsonrpc_core::Error> {
-    let message_buf = request(String::new(), data_buf);
-    let (res_tx, res_rx) = crossbeam::channel::bounded(1);
-    let mut capture_vars = Some((res_tx, message_buf));
-    ws::connect(format!("ws://localhost:{}", port), move |out| {
-        // Even though this closure is only called once, the API requires FnMut
-        // so we must use a workaround to take ownership of our captured variables
-        let (res_tx, message_buf) = capture_vars.take().unwrap();
-
-        let send_response = match out.send(message_buf) {
-            Ok(()) => true,
-            Err(e) => {
-                res_tx.send(Err(internal_error(format!("failed to send message along conductor interface: {}", e)))).unwrap();
-                if let Err(e) = out.close(ws::CloseCode::Error) {
-                    println!("warning: silently ignoring error: failed to close conductor interface connection: {}", e);
+    remote_call_with_timeout(port, data_buf, DEFAULT_REMOTE_CALL_TIMEOUT, 0)
+}
+
+// A timed-out response is never retried, since the conductor may already be
+// acting on it, and neither is a bad or missing response to a request that was
+// sent; a connect/send failure is, since the call never left this process.
+enum RemoteCallError {
+    Timeout,
+    ConnectOrSend(jsonrpc_core::Error),
+    Response(jsonrpc_core::Error),
+}
+
+/// Like `remote_call`, but bounds how long to wait for a response and retries
+/// up to `retries` times, with linear backoff, on connect/send failure.
+pub fn remote_call_with_timeout(
+    port: u16,
+    data_buf: Vec<u8>,
+    timeout: Duration,
+    retries: u32,
+) -> Result<Vec<u8>, jsonrpc_core::Error> {
+    let mut attempt = 0;
+    loop {
+        match try_remote_call(port, &data_buf, timeout) {
+            Ok(response) => return Ok(response),
+            Err(RemoteCallError::Timeout) => {
+                return Err(internal_error(format!(
+                    "timed out waiting for a response from the conductor interface after {:?}",
+                    timeout
+                )))
+            }
+            Err(RemoteCallError::Response(e)) => return Err(e),
+            Err(RemoteCallError::ConnectOrSend(e)) => {
+                if attempt >= retries {
+                    return Err(e);
                 }
-                false
+                attempt += 1;
+                thread::sleep(RETRY_BACKOFF * attempt);
             }
-        };
-        move |response| {
-            if send_response {
-                res_tx.send(Ok(response)).unwrap();
-                out.close(ws::CloseCode::Normal)
-            } else {
-                println!("warning: ignoring conductor interface response");
-                Ok(())
+        }
+    }
+}
+
+fn try_remote_call(
+    port: u16,
+    data_buf: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, RemoteCallError> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+        RemoteCallError::ConnectOrSend(internal_error(format!(
+            "failed to start conductor client runtime: {}",
+            e
+        )))
+    })?;
+    let message = Message::Request {
+        id: String::new(),
+        data: serde_bytes::ByteBuf::from(data_buf.to_vec()),
+    };
+
+    runtime
+        .block_on(tokio::time::timeout(timeout, async move {
+            let mut connection = connect_codec(port)
+                .await
+                .map_err(RemoteCallError::ConnectOrSend)?;
+            connection.send(message).await.map_err(|e| {
+                RemoteCallError::ConnectOrSend(internal_error(format!(
+                    "failed to send message along conductor interface: {}",
+                    e
+                )))
+            })?;
+            match connection.next().await {
+                Some(Ok(Message::Response { data, .. })) => Ok(data.into_vec()),
+                Some(Ok(r)) => Err(RemoteCallError::Response(internal_error(format!(
+                    "unexpected message type from conductor: {:?}",
+                    r
+                )))),
+                Some(Err(e)) => Err(RemoteCallError::Response(internal_error(format!(
+                    "failed to parse conductor response: {}",
+                    e
+                )))),
+                None => Err(RemoteCallError::Response(internal_error(
+                    "conductor interface connection closed before responding".to_string(),
+                ))),
             }
+        }))
+        .unwrap_or(Err(RemoteCallError::Timeout))
+}
+
+/// A persistent, multiplexed connection to a conductor interface: unlike
+/// `remote_call`, which opens and tears down a WebSocket per call,
+/// `ConductorClient` keeps one connection open and matches concurrent
+/// `call`s to their responses by request id.
+pub struct ConductorClient {
+    request_tx: crossbeam::channel::Sender<Message>,
+    responses_awaited: Arc<Mutex<HashMap<String, crossbeam::channel::Sender<Result<Vec<u8>, String>>>>>,
+    next_id: AtomicU64,
+}
+
+impl ConductorClient {
+    pub fn connect(port: u16) -> Result<Self, jsonrpc_core::Error> {
+        let (request_tx, request_rx) = crossbeam::channel::unbounded::<Message>();
+        let responses_awaited: Arc<
+            Mutex<HashMap<String, crossbeam::channel::Sender<Result<Vec<u8>, String>>>>,
+        > = Arc::new(Mutex::new(HashMap::new()));
+        let responses_awaited_reader = responses_awaited.clone();
+
+        let (connect_tx, connect_rx) = crossbeam::channel::bounded(1);
+        thread::spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = connect_tx.send(Err(internal_error(format!(
+                        "failed to start conductor client runtime: {}",
+                        e
+                    ))));
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                let connection = match connect_codec(port).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        let _ = connect_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = connect_tx.send(Ok(()));
+                let (mut sink, mut stream) = connection.split();
+
+                let write_loop = async {
+                    // `try_recv`, not `recv`: a blocking wait here would stall
+                    // `read_loop`, which runs on the same task.
+                    loop {
+                        let msg = match request_rx.try_recv() {
+                            Ok(msg) => msg,
+                            Err(crossbeam::channel::TryRecvError::Empty) => {
+                                tokio::time::sleep(Duration::from_millis(10)).await;
+                                continue;
+                            }
+                            Err(crossbeam::channel::TryRecvError::Disconnected) => break,
+                        };
+                        if let Err(e) = sink.send(msg).await {
+                            println!(
+                                "warning: failed to send message along conductor interface: {}",
+                                e
+                            );
+                            break;
+                        }
+                    }
+                };
+                let read_loop = async {
+                    while let Some(frame) = stream.next().await {
+                        match frame {
+                            Ok(Message::Response { id, data }) => {
+                                match responses_awaited_reader.lock().unwrap().remove(&id) {
+                                    Some(sender) => {
+                                        let _ = sender.send(Ok(data.into_vec()));
+                                    }
+                                    None => println!(
+                                        "warning: received unexpected response from conductor interface; dropping"
+                                    ),
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => println!(
+                                "warning: could not parse message from conductor interface: {}",
+                                e
+                            ),
+                        }
+                    }
+                };
+                // `select!`, not `join`: if the sink errors out (e.g. the conductor
+                // closed the connection), `write_loop` returns but `stream` can stay
+                // open indefinitely, and `join` would wait for both forever.
+                tokio::select! {
+                    _ = write_loop => {}
+                    _ = read_loop => {}
+                }
+
+                // Once the connection is gone, no further `Message::Response` will
+                // ever arrive to wake callers still waiting in `call` - fail them
+                // now instead of leaving them blocked on `res_rx.recv()` forever.
+                for (_, sender) in responses_awaited_reader.lock().unwrap().drain() {
+                    let _ = sender.send(Err(
+                        "conductor interface connection closed before responding".to_string(),
+                    ));
+                }
+            });
+        });
+
+        connect_rx
+            .recv()
+            .map_err(|_| {
+                internal_error(
+                    "conductor client background thread exited before connecting".to_string(),
+                )
+            })??;
+
+        Ok(ConductorClient {
+            request_tx,
+            responses_awaited,
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    /// Sends `data_buf` under a freshly generated request id and blocks until
+    /// the matching response arrives over the shared connection.
+    pub fn call(&self, data_buf: Vec<u8>) -> Result<Vec<u8>, jsonrpc_core::Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+        let (res_tx, res_rx) = crossbeam::channel::bounded(1);
+        self.responses_awaited
+            .lock()
+            .unwrap()
+            .insert(id.clone(), res_tx);
+
+        if self
+            .request_tx
+            .send(Message::Request {
+                id: id.clone(),
+                data: data_buf.into(),
+            })
+            .is_err()
+        {
+            self.responses_awaited.lock().unwrap().remove(&id);
+            return Err(internal_error(
+                "conductor interface connection is closed".to_string(),
+            ));
         }
-    }).map_err(|e| internal_error(format!("failed to connect to conductor interface: {}", e)))?;
 
-    let response = res_rx.recv().unwrap()?;
-    parse_holochain_response(response)
-        .map_err(|e| internal_error(format!("failed to parse conductor response: {}", e)))
+        res_rx
+            .recv()
+            .map_err(|_| {
+                internal_error(
+                    "conductor interface connection closed before responding".to_string(),
+                )
+            })?
+            .map_err(|e| internal_error(format!("failed to parse conductor response: {}", e)))
+    }
+}
+
+/// A registered interest in incoming signals: `filter` is tested against each
+/// signal decoded from MessagePack, and matches are sent to `sender`. The
+/// subscription is dropped the first time a matching signal cannot be sent
+/// because its receiver has disconnected.
+struct SignalSubscription {
+    filter: Box<dyn Fn(&serde_json::Value) -> bool + Send>,
+    sender: crossbeam::channel::Sender<serde_json::Value>,
+}
+
+/// A signal captured by `AppConnection`, along with the time it was received
+/// so the buffer can evict it once it exceeds the configured TTL.
+pub struct AccumulatedSignal {
+    pub received_at: Instant,
+    pub signal: serde_json::Value,
 }
 
 pub struct AppConnection {
-    // Contains the base64-encoded payload of each message of type "Signal" received since last polled by tryorama
-    pub signals_accumulated: Vec<serde_json::Value>,
-    pub responses_awaited: HashMap<String, crossbeam::channel::Sender<ws::Result<String>>>,
+    // Accumulated signals, base64-encoded, since tryorama last called poll_signals().
+    // Private so capacity/TTL eviction can't be bypassed by draining the field directly;
+    // use poll_signals() instead.
+    signals_accumulated: VecDeque<AccumulatedSignal>,
+    pub responses_awaited: HashMap<String, crossbeam::channel::Sender<Result<String, String>>>,
+    signal_subscriptions: Vec<SignalSubscription>,
+    signal_capacity: usize,
+    signal_ttl: Option<Duration>,
 }
 
+impl AppConnection {
+    /// `signal_capacity` bounds how many signals are held at once, evicting
+    /// the oldest once exceeded; `signal_ttl`, if set, additionally evicts
+    /// entries older than the given duration on each push or poll.
+    pub fn new(signal_capacity: usize, signal_ttl: Option<Duration>) -> Self {
+        AppConnection {
+            signals_accumulated: VecDeque::new(),
+            responses_awaited: HashMap::new(),
+            signal_subscriptions: Vec::new(),
+            signal_capacity,
+            signal_ttl,
+        }
+    }
+
+    /// Registers interest in signals matching `filter`, which is tested
+    /// against the signal decoded from MessagePack (e.g. by cell, zome, or
+    /// payload content). Matching signals are sent to the returned receiver
+    /// as they arrive, instead of the caller polling and filtering
+    /// `poll_signals` results client-side.
+    pub fn subscribe_signals(
+        &mut self,
+        filter: impl Fn(&serde_json::Value) -> bool + Send + 'static,
+    ) -> crossbeam::channel::Receiver<serde_json::Value> {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        self.signal_subscriptions.push(SignalSubscription {
+            filter: Box::new(filter),
+            sender,
+        });
+        receiver
+    }
+
+    fn push_signal(&mut self, signal: serde_json::Value) {
+        self.evict_expired_signals();
+        self.signals_accumulated.push_back(AccumulatedSignal {
+            received_at: Instant::now(),
+            signal,
+        });
+        while self.signals_accumulated.len() > self.signal_capacity {
+            self.signals_accumulated.pop_front();
+        }
+    }
+
+    fn evict_expired_signals(&mut self) {
+        let ttl = match self.signal_ttl {
+            Some(ttl) => ttl,
+            None => return,
+        };
+        let now = Instant::now();
+        while self
+            .signals_accumulated
+            .front()
+            .map_or(false, |entry| now.duration_since(entry.received_at) > ttl)
+        {
+            self.signals_accumulated.pop_front();
+        }
+    }
+
+    /// Returns the currently accumulated signals, first evicting any entries
+    /// past their TTL.
+    pub fn poll_signals(&mut self) -> Vec<serde_json::Value> {
+        self.evict_expired_signals();
+        self.signals_accumulated
+            .drain(..)
+            .map(|entry| entry.signal)
+            .collect()
+    }
+}
+
+/// Like `ConductorClient`, but for an app interface: `connected_callback` is
+/// handed a sender for outgoing `Message`s (e.g. zome call requests) instead
+/// of a raw `ws::Sender`, and gets back the `AppConnection` that accumulates
+/// signals and matches responses to `responses_awaited` by request id.
 pub fn connect_app_interface(
     port: u16,
-    connected_callback: impl FnOnce(ws::Sender) -> Arc<Mutex<AppConnection>> + Send + 'static,
+    signal_capacity: usize,
+    signal_ttl: Option<Duration>,
+    connected_callback: impl FnOnce(
+            crossbeam::channel::Sender<Message>,
+            usize,
+            Option<Duration>,
+        ) -> Arc<Mutex<AppConnection>>
+        + Send
+        + 'static,
 ) {
     thread::spawn(move || {
-        let mut on_connect = Some(|handle| {
-            let connection = connected_callback(handle);
-            move |message| {
-                match parse_holochain_message(message) {
-                    Ok(Message::Signal { data }) => {
-                        let encoded = base64::encode(data);
-                        connection
-                            .lock()
-                            .unwrap()
-                            .signals_accumulated
-                            .push(serde_json::Value::String(encoded));
-                    }
-                    Ok(Message::Response { id, data }) => {
-                        let encoded = base64::encode(data);
-                        match connection.lock().unwrap()
-                        .responses_awaited
-                        .remove(&id)
-                    {
-                        Some(sender) => sender.send(Ok(encoded)).unwrap(),
-                        None => {
-                            println!("warning: received unexpected response from app interface; dropping")
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                println!(
+                    "warning: silently ignoring error: failed to start app interface runtime: {}",
+                    e
+                );
+                return;
+            }
+        };
+        runtime.block_on(async move {
+            let connection = match connect_codec(port).await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    println!(
+                        "warning: silently ignoring error: failed to connect to app interface: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+            let (mut sink, mut stream) = connection.split();
+
+            let (request_tx, request_rx) = crossbeam::channel::unbounded::<Message>();
+            let app_connection = connected_callback(request_tx, signal_capacity, signal_ttl);
+
+            let write_loop = async {
+                // `try_recv`, not `recv`: a blocking wait here would stall
+                // `read_loop`, which runs on the same task.
+                loop {
+                    let msg = match request_rx.try_recv() {
+                        Ok(msg) => msg,
+                        Err(crossbeam::channel::TryRecvError::Empty) => {
+                            tokio::time::sleep(Duration::from_millis(10)).await;
+                            continue;
                         }
+                        Err(crossbeam::channel::TryRecvError::Disconnected) => break,
+                    };
+                    if let Err(e) = sink.send(msg).await {
+                        println!("warning: failed to send message along app interface: {}", e);
+                        break;
                     }
+                }
+            };
+            let read_loop = async {
+                while let Some(frame) = stream.next().await {
+                    match frame {
+                        Ok(Message::Signal { data }) => {
+                            let raw = data.into_vec();
+                            let mut guard = app_connection.lock().unwrap();
+                            if !guard.signal_subscriptions.is_empty() {
+                                match rmp_serde::from_slice::<serde_json::Value>(&raw) {
+                                    Ok(decoded) => guard.signal_subscriptions.retain_mut(|subscription| {
+                                        if !(subscription.filter)(&decoded) {
+                                            return true;
+                                        }
+                                        subscription.sender.send(decoded.clone()).is_ok()
+                                    }),
+                                    Err(e) => println!(
+                                        "warning: could not decode signal for subscription matching: {}",
+                                        e
+                                    ),
+                                }
+                            }
+                            let encoded = base64::encode(&raw);
+                            guard.push_signal(serde_json::Value::String(encoded));
+                        }
+                        Ok(Message::Response { id, data }) => {
+                            let encoded = base64::encode(data);
+                            match app_connection.lock().unwrap().responses_awaited.remove(&id) {
+                                Some(sender) => {
+                                    let _ = sender.send(Ok(encoded));
+                                }
+                                None => println!(
+                                    "warning: received unexpected response from app interface; dropping"
+                                ),
+                            }
+                        }
+                        Ok(Message::Request { .. }) => println!(
+                            "warning: received unexpected request from app interface; dropping"
+                        ),
+                        Err(e) => println!(
+                            "warning: could not parse message from app interface: {}",
+                            e
+                        ),
                     }
-                    Ok(Message::Request { .. }) => println!(
-                        "warning: received unexpected request from app interface; dropping"
-                    ),
-                    Err(e) => println!(
-                        "warning: could not parse message from app interface: {:?}",
-                        e
-                    ),
-                };
-                Ok(())
+                }
+            };
+            tokio::select! {
+                _ = write_loop => {}
+                _ = read_loop => {}
+            }
+
+            // Once the connection is gone, no further `Message::Response` will
+            // ever arrive to wake callers still waiting on a response - fail
+            // them now instead of leaving them blocked forever.
+            for (_, sender) in app_connection.lock().unwrap().responses_awaited.drain() {
+                let _ = sender.send(Err(
+                    "app interface connection closed before responding".to_string(),
+                ));
             }
         });
-        let res = ws::connect(format!("ws://localhost:{}", port), |handle| {
-            on_connect.take().unwrap()(handle)
-        });
-        if let Err(e) = res {
-            println!(
-                "warning: silently ignoring error: failed to connect to app interface: {}",
-                e
-            )
-        };
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_signal_evicts_oldest_past_capacity() {
+        let mut conn = AppConnection::new(2, None);
+        conn.push_signal(serde_json::json!(1));
+        conn.push_signal(serde_json::json!(2));
+        conn.push_signal(serde_json::json!(3));
+        assert_eq!(
+            conn.poll_signals(),
+            vec![serde_json::json!(2), serde_json::json!(3)]
+        );
+    }
+
+    #[test]
+    fn push_signal_evicts_expired_by_ttl() {
+        let mut conn = AppConnection::new(10, Some(Duration::from_millis(20)));
+        conn.push_signal(serde_json::json!("old"));
+        thread::sleep(Duration::from_millis(40));
+        conn.push_signal(serde_json::json!("new"));
+        assert_eq!(conn.poll_signals(), vec![serde_json::json!("new")]);
+    }
+
+    #[test]
+    fn poll_signals_evicts_expired_even_without_a_new_push() {
+        let mut conn = AppConnection::new(10, Some(Duration::from_millis(20)));
+        conn.push_signal(serde_json::json!("old"));
+        thread::sleep(Duration::from_millis(40));
+        assert!(conn.poll_signals().is_empty());
+    }
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn remote_call_times_out_without_retrying() {
+        let port = free_port();
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port)).unwrap();
+        thread::spawn(move || {
+            if let Ok((stream, _)) = listener.accept() {
+                // Complete the WebSocket handshake, then never send a
+                // response - the call should time out waiting for one.
+                let _socket = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+                thread::sleep(Duration::from_secs(5));
+            }
+        });
+
+        let start = Instant::now();
+        let result = remote_call_with_timeout(port, vec![], Duration::from_millis(100), 5);
+        assert!(result.is_err());
+        // A timeout is never retried, so this should take about one
+        // `timeout` interval, not `retries` of them.
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn remote_call_retries_on_connect_failure() {
+        // Nothing is listening on this port, so every attempt fails to connect.
+        let port = free_port();
+        let start = Instant::now();
+        let result = remote_call_with_timeout(port, vec![], Duration::from_millis(50), 3);
+        assert!(result.is_err());
+        // Backoff is `RETRY_BACKOFF * attempt`, so 3 retries take at least
+        // 1 + 2 + 3 = 6 backoff units.
+        assert!(start.elapsed() >= RETRY_BACKOFF * 6);
+    }
+}